@@ -1,36 +1,421 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
 use pyo3::prelude::*;
 use pyo3::PyObjectProtocol;
 use pyo3::PyIterProtocol;
+use pyo3::PyMappingProtocol;
 use pyo3::exceptions;
-use pyo3::types::PyBool;
+use pyo3::types::{PyBool, PyList, PySlice, PyTuple};
 use pyo3::class::number::PyNumberProtocol;
 use pyo3::class::sequence::PySequenceProtocol;
 use pyo3::class::basic::CompareOp;
 
 
-#[pyclass]
-#[derive(Copy, Clone)]
-struct Vector2 {
-    #[pyo3(get)]
-    x: f64,
+/// Define a `wvec` vector pyclass together with the arithmetic, comparison,
+/// hashing, pickling, iteration and indexing behaviour shared by every
+/// vector type in this crate. `$unit` is the fallback unit vector returned
+/// by `normalized()` for a zero-length vector. Geometry methods that differ
+/// by dimension (e.g. `cross`, `angle_between`) are implemented outside the
+/// macro, per type; `rotate` exists only on `Vector2`.
+macro_rules! vector_type {
+    ($name:ident, $iter:ident, [$($field:ident),+], $unit:expr) => {
+
+        #[pyclass(module = "wvec")]
+        #[derive(Copy, Clone)]
+        struct $name {
+            $(
+                #[pyo3(get)]
+                $field: f64,
+            )+
+        }
+
+
+        impl $name {
+            /// Return the component at idx, supporting negative indices as
+            /// Python does for sequences.
+            fn component(&self, idx: isize) -> PyResult<f64> {
+                let arr = [$(self.$field),+];
+                let i = if idx < 0 { idx + arr.len() as isize } else { idx };
+                if i < 0 || i as usize >= arr.len() {
+                    Err(exceptions::IndexError::py_err(
+                        concat!(stringify!($name), " index out of range")
+                    ))
+                } else {
+                    Ok(arr[i as usize])
+                }
+            }
+        }
+
+
+        #[pymethods]
+        impl $name {
+            #[new]
+            fn new($($field: f64),+) -> PyResult<Self> {
+                if true $(&& $field.is_finite())* {
+                    Ok($name { $($field),+ })
+                } else {
+                    Err(exceptions::ValueError::py_err(
+                        "component values may not be NaN/inf"
+                    ))
+                }
+            }
+
+            /// Return True if this vector is the zero vector.
+            ///
+            /// Note that bool(vec) will always return True, because a
+            /// vector is a sequence of nonzero length.
+            fn is_zero(&self) -> bool {
+                true $(&& self.$field == 0.0)*
+            }
+
+            /// Return the length of the vector, squared.
+            ///
+            /// This is minutely faster than getting the length and is
+            /// sufficient for some comparison purposes.
+            fn length_squared(&self) -> f64 {
+                self.dot(&self)
+            }
+
+            /// Return the length of the vector.
+            fn length(&self) -> f64 {
+                self.length_squared().sqrt()
+            }
+
+            /// Return a normalized copy of this vector.
+            ///
+            /// If the vector is of zero length then an arbitrary unit
+            /// vector is returned.
+            fn normalized(&self) -> Self {
+                if self.is_zero() {
+                    return $unit;
+                }
+                let mag = self.length();
+                $name { $($field: self.$field / mag),+ }
+            }
+
+            fn dot(&self, other: &$name) -> f64 {
+                0.0 $(+ self.$field * other.$field)*
+            }
+
+            /// Return the component-wise minimum of this vector and other.
+            fn min(&self, other: &$name) -> Self {
+                $name { $($field: self.$field.min(other.$field)),+ }
+            }
+
+            /// Return the component-wise maximum of this vector and other.
+            fn max(&self, other: &$name) -> Self {
+                $name { $($field: self.$field.max(other.$field)),+ }
+            }
+
+            /// Return a copy of this vector with each component replaced by
+            /// its absolute value.
+            fn abs(&self) -> Self {
+                $name { $($field: self.$field.abs()),+ }
+            }
+
+            /// Return a copy of this vector with each component clamped into
+            /// the box defined by lo and hi.
+            fn clamp(&self, lo: &$name, hi: &$name) -> Self {
+                $name { $($field: self.$field.max(lo.$field).min(hi.$field)),+ }
+            }
+
+            /// Return the linear interpolation between this vector and
+            /// other at t.
+            ///
+            /// t=0 returns this vector, t=1 returns other.
+            fn lerp(&self, other: &$name, t: f64) -> Self {
+                $name { $($field: self.$field + (other.$field - self.$field) * t),+ }
+            }
+
+            /// Return the squared distance between this vector and other.
+            ///
+            /// This is minutely faster than `distance` and is sufficient
+            /// for some comparison purposes.
+            fn distance_squared(&self, other: &$name) -> f64 {
+                let diff = $name { $($field: self.$field - other.$field),+ };
+                diff.length_squared()
+            }
+
+            /// Return the distance between this vector and other.
+            fn distance(&self, other: &$name) -> f64 {
+                self.distance_squared(other).sqrt()
+            }
+
+            /// Return a copy of this vector, scaled down so its length does
+            /// not exceed max. If the vector is already shorter than max it
+            /// is returned unchanged.
+            fn clamp_length(&self, max: f64) -> Self {
+                let len = self.length();
+                if len <= max || len == 0.0 {
+                    return *self;
+                }
+                let scale = max / len;
+                $name { $($field: self.$field * scale),+ }
+            }
+
+            /// Support copy.copy().
+            fn __copy__(&self) -> Self {
+                *self
+            }
+
+            /// Support copy.deepcopy(). This type has no nested objects, so
+            /// this is equivalent to __copy__.
+            fn __deepcopy__(&self, _memo: &PyAny) -> Self {
+                *self
+            }
+
+            /// Support pickling.
+            fn __reduce__(&self) -> PyResult<(PyObject, PyObject)> {
+                let gil = pyo3::Python::acquire_gil();
+                let py = gil.python();
+                let args = PyTuple::new(py, &[$(self.$field),+]);
+                Ok((py.get_type::<$name>().into(), args.into()))
+            }
+        }
+
+
+        #[pyproto]
+        impl PyObjectProtocol for $name {
+            fn __repr__(&self) -> String {
+                let parts: Vec<String> = vec![$(self.$field.to_string()),+];
+                format!("{}({})", stringify!($name), parts.join(", "))
+            }
+
+            fn __str__(&self) -> String {
+                self.__repr__()
+            }
+
+            /// Hash this vector from the bit patterns of its components, with
+            /// -0.0 hashed as 0.0 so the hash is consistent with __richcmp__
+            /// equality between vectors. Note that only instances of this
+            /// type are hashable: a vector compares equal to an equivalent
+            /// tuple/list, but those do not share its hash.
+            fn __hash__(&self) -> u64 {
+                let mut hasher = DefaultHasher::new();
+                $(
+                    let normalized = if self.$field == 0.0 { 0.0 } else { self.$field };
+                    normalized.to_bits().hash(&mut hasher);
+                )+
+                hasher.finish()
+            }
+
+            fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyObject {
+                let gil = pyo3::Python::acquire_gil();
+                let py = gil.python();
+
+                let cmp: bool = match op {
+                    CompareOp::Eq => false,
+                    CompareOp::Ne => true,
+                    _ => {
+                        return py.NotImplemented();
+                    }
+                };
+
+                if let Ok(v) = other.extract::<$name>() {
+                    let eq = true $(&& self.$field == v.$field)*;
+                    return PyBool::new(py, eq ^ cmp).into();
+                }
+
+                match other.extract::<Vec<f64>>() {
+                    Ok(vals) => {
+                        let arr = [$(self.$field),+];
+                        let eq = vals.len() == arr.len()
+                                 && vals.iter().zip(arr.iter()).all(|(a, b)| a == b);
+                        PyBool::new(py, eq ^ cmp).into()
+                    },
+                    Err(_) => {
+                        py.NotImplemented()
+                    }
+                }
+            }
+        }
+
+
+        #[pyproto]
+        impl PyNumberProtocol for $name {
+            fn __add__(lhs: PyRef<'p, $name>, rhs: &'p PyAny) -> PyObject {
+                let gil = pyo3::Python::acquire_gil();
+                let py = gil.python();
+
+                match rhs.extract::<$name>() {
+                    Ok(rhs) => $name { $($field: lhs.$field + rhs.$field),+ }.into_py(py),
+                    Err(_) => py.NotImplemented(),
+                }
+            }
+
+            fn __sub__(lhs: PyRef<'p, $name>, rhs: &'p PyAny) -> PyObject {
+                let gil = pyo3::Python::acquire_gil();
+                let py = gil.python();
+
+                match rhs.extract::<$name>() {
+                    Ok(rhs) => $name { $($field: lhs.$field - rhs.$field),+ }.into_py(py),
+                    Err(_) => py.NotImplemented(),
+                }
+            }
+
+            fn __neg__(&self) -> $name {
+                $name { $($field: -self.$field),+ }
+            }
+
+            /// Scalar multiplication, accepting either `vector * scalar` or
+            /// the reflected `scalar * vector` (pyo3's nb_multiply slot is
+            /// shared between `__mul__`/`__rmul__`, so both orderings are
+            /// handled here).
+            fn __mul__(lhs: &'p PyAny, rhs: &'p PyAny) -> PyResult<PyObject> {
+                let gil = pyo3::Python::acquire_gil();
+                let py = gil.python();
+
+                if let Ok(v) = lhs.extract::<$name>() {
+                    if let Ok(s) = rhs.extract::<f64>() {
+                        let out = $name { $($field: v.$field * s),+ };
+                        return if true $(&& out.$field.is_finite())* {
+                            Ok(out.into_py(py))
+                        } else {
+                            Err(exceptions::ValueError::py_err(
+                                "multiplication produced a NaN/inf result"
+                            ))
+                        };
+                    }
+                }
+                if let Ok(v) = rhs.extract::<$name>() {
+                    if let Ok(s) = lhs.extract::<f64>() {
+                        let out = $name { $($field: v.$field * s),+ };
+                        return if true $(&& out.$field.is_finite())* {
+                            Ok(out.into_py(py))
+                        } else {
+                            Err(exceptions::ValueError::py_err(
+                                "multiplication produced a NaN/inf result"
+                            ))
+                        };
+                    }
+                }
+                Ok(py.NotImplemented())
+            }
+
+            fn __truediv__(lhs: PyRef<'p, $name>, rhs: f64) -> PyResult<$name> {
+                let out = $name { $($field: lhs.$field / rhs),+ };
+                if true $(&& out.$field.is_finite())* {
+                    Ok(out)
+                } else {
+                    Err(exceptions::ValueError::py_err(
+                        "division produced a NaN/inf result"
+                    ))
+                }
+            }
+
+            fn __iadd__(&mut self, other: $name) {
+                $(self.$field += other.$field;)+
+            }
+
+            fn __isub__(&mut self, other: $name) {
+                $(self.$field -= other.$field;)+
+            }
+
+            fn __imul__(&mut self, other: f64) -> PyResult<()> {
+                let out = $name { $($field: self.$field * other),+ };
+                if true $(&& out.$field.is_finite())* {
+                    $(self.$field = out.$field;)+
+                    Ok(())
+                } else {
+                    Err(exceptions::ValueError::py_err(
+                        "multiplication produced a NaN/inf result"
+                    ))
+                }
+            }
+        }
+
+
+        #[pyproto]
+        impl PySequenceProtocol for $name {
+            fn __len__(&self) -> usize {
+                [$(self.$field),+].len()
+            }
+        }
+
+
+        #[pyproto]
+        impl PyMappingProtocol for $name {
+            fn __getitem__(&self, idx: &PyAny) -> PyResult<PyObject> {
+                let gil = pyo3::Python::acquire_gil();
+                let py = gil.python();
+                let len = [$(self.$field),+].len();
+
+                if let Ok(slice) = idx.downcast::<PySlice>() {
+                    let indices = slice.indices(len as i64)?;
+                    let mut out = Vec::new();
+                    let mut i = indices.start;
+                    if indices.step > 0 {
+                        while i < indices.stop {
+                            out.push(self.component(i)?);
+                            i += indices.step;
+                        }
+                    } else {
+                        while i > indices.stop {
+                            out.push(self.component(i)?);
+                            i += indices.step;
+                        }
+                    }
+                    return Ok(PyList::new(py, out).into_py(py));
+                }
+
+                let i: isize = idx.extract()?;
+                Ok(self.component(i)?.into_py(py))
+            }
+        }
+
+
+        #[pyclass]
+        struct $iter {
+            v: $name,
+            pos: usize,
+        }
+
+
+        #[pyproto]
+        impl PyIterProtocol for $iter {
+            fn __iter__(slf: PyRef<Self>) -> Py<$iter> {
+                slf.into()
+            }
+            fn __next__(mut slf: PyRefMut<Self>) -> Option<f64> {
+                let arr = [$(slf.v.$field),+];
+                let res = arr.get(slf.pos).copied();
+                slf.pos += 1;
+                res
+            }
+        }
 
-    #[pyo3(get)]
-    y: f64,
+
+        #[pyproto]
+        impl PyIterProtocol for $name {
+            fn __iter__(slf: PyRef<Self>) -> $iter {
+                $iter {
+                    v: *slf,
+                    pos: 0,
+                }
+            }
+        }
+    }
 }
 
 
+vector_type!(Vector2, VecIter, [x, y], Vector2 { x: 1.0, y: 0.0 });
+vector_type!(Vector3, Vec3Iter, [x, y, z], Vector3 { x: 1.0, y: 0.0, z: 0.0 });
+
+
 #[pymethods]
 impl Vector2 {
-    #[new]
-    fn new(x: f64, y: f64) -> PyResult<Self> {
-        if x.is_finite() && y.is_finite() {
-            Ok(Vector2 { x, y })
-        } else {
-            Err(exceptions::ValueError::py_err(
-                "x/y values may not be NaN/inf"
-            ))
-        }
-    }
+    #[classattr]
+    const ZERO: Vector2 = Vector2 { x: 0.0, y: 0.0 };
+
+    #[classattr]
+    const ONE: Vector2 = Vector2 { x: 1.0, y: 1.0 };
+
+    #[classattr]
+    const X: Vector2 = Vector2 { x: 1.0, y: 0.0 };
+
+    #[classattr]
+    const Y: Vector2 = Vector2 { x: 0.0, y: 1.0 };
 
     /// Construct a new cartesian vector from r (length) and theta (angle).
     #[text_signature = "(r: float, theta: float)"]
@@ -41,27 +426,6 @@ impl Vector2 {
         Ok(Vector2 {x, y})
     }
 
-    /// Return True if this vector is the zero vector.
-    ///
-    /// Note that bool(vec) will always return True, because a Vector2 is a
-    /// sequence of length 2.
-    fn is_zero(&self) -> bool {
-        return self.x == 0.0 && self.y == 0.0
-    }
-
-    /// Return the length of the vector, squared.
-    ///
-    /// This is minutely faster than getting the length and is sufficient for
-    /// some comparison purposes.
-    fn length_squared(&self) -> f64 {
-        self.dot(&self)
-    }
-
-    /// Return the length of the vector.
-    fn length(&self) -> f64 {
-        self.length_squared().sqrt()
-    }
-
     /// Return the angle this vector makes to the positive x axis.
     fn angle(&self) -> f64 {
         self.y.atan2(self.x)
@@ -72,136 +436,384 @@ impl Vector2 {
         (self.length(), self.angle())
     }
 
-    /// Return a normalized copy of this vector.
-    ///
-    /// If the vector is of zero length then an arbitrary zero-length vector
-    /// is returned.
-    fn normalized(&self) -> Self {
-        if self.is_zero() {
-            return Vector2 { x: 1.0, y: 0.0 }
+    /// Return the z-component of the 3D cross product of this vector with other.
+    fn cross(&self, other: &Vector2) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Return a copy of this vector rotated anticlockwise by theta radians.
+    fn rotate(&self, theta: f64) -> Self {
+        let (sin, cos) = theta.sin_cos();
+        Vector2 {
+            x: self.x * cos - self.y * sin,
+            y: self.x * sin + self.y * cos,
         }
-        let mag = self.length();
+    }
 
+    /// Return this vector reflected about the given normal.
+    fn reflect(&self, normal: &Vector2) -> Self {
+        let n = normal.normalized();
+        let d = 2.0 * self.dot(&n);
         Vector2 {
-            x: self.x / mag,
-            y: self.y / mag,
+            x: self.x - n.x * d,
+            y: self.y - n.y * d,
+        }
+    }
+
+    /// Return the projection of this vector onto other.
+    fn project(&self, onto: &Vector2) -> PyResult<Self> {
+        let len_sq = onto.length_squared();
+        if len_sq == 0.0 {
+            return Err(exceptions::ValueError::py_err(
+                "cannot project onto the zero vector"
+            ));
         }
+        let scale = self.dot(onto) / len_sq;
+        Ok(Vector2 {
+            x: onto.x * scale,
+            y: onto.y * scale,
+        })
     }
 
-    fn dot(&self, other: &Vector2) -> f64 {
-        self.x * other.x + self.y * other.y
+    /// Return the component of this vector perpendicular to other.
+    fn reject(&self, onto: &Vector2) -> PyResult<Self> {
+        let proj = self.project(onto)?;
+        Ok(Vector2 {
+            x: self.x - proj.x,
+            y: self.y - proj.y,
+        })
+    }
+
+    /// Return the signed angle in radians from this vector to other.
+    fn angle_between(&self, other: &Vector2) -> f64 {
+        self.cross(other).atan2(self.dot(other))
     }
 }
 
 
-#[pyproto]
-impl PyObjectProtocol for Vector2 {
-    fn __repr__(&self) -> String {
-        format!("Vector2({}, {})", self.x, self.y)
+#[pymethods]
+impl Vector3 {
+    /// Return the 3D cross product of this vector with other.
+    fn cross(&self, other: &Vector3) -> Self {
+        Vector3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    /// Return the unsigned angle in radians between this vector and other.
+    fn angle_between(&self, other: &Vector3) -> f64 {
+        (self.dot(other) / (self.length() * other.length())).acos()
     }
+}
+
+
+#[pymodule]
+fn wvec(_py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<Vector2>()?;
+    m.add_class::<Vector3>()?;
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::IntoPyDict;
 
-    fn __str__(&self) -> String {
-        self.__repr__()
+    fn locals<'p>(py: Python<'p>, v: Vector2, w: Vector2) -> &'p pyo3::types::PyDict {
+        [("v", PyCell::new(py, v).unwrap()), ("w", PyCell::new(py, w).unwrap())].into_py_dict(py)
     }
 
-    fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyObject {
-        let gil = pyo3::Python::acquire_gil();
+    #[test]
+    fn mul_by_huge_scalar_raises() {
+        let gil = Python::acquire_gil();
         let py = gil.python();
+        let locals = locals(py, Vector2 { x: 0.0, y: 1.0 }, Vector2::ZERO);
+        let err = py.eval("v * 1e308 * 1e308", None, Some(locals)).unwrap_err();
+        assert!(err.is_instance::<exceptions::ValueError>(py));
+    }
 
+    #[test]
+    fn rmul_by_huge_scalar_raises() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let locals = locals(py, Vector2 { x: 0.0, y: 1.0 }, Vector2::ZERO);
+        let err = py.eval("(1e308 * 1e308) * v", None, Some(locals)).unwrap_err();
+        assert!(err.is_instance::<exceptions::ValueError>(py));
+    }
 
-        let cmp: bool = match op {
-            CompareOp::Eq => false,
-            CompareOp::Ne => true,
-            _ => {
-                return py.NotImplemented();
-            }
-        };
+    #[test]
+    fn truediv_by_zero_raises() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let locals = locals(py, Vector2 { x: 1.0, y: 1.0 }, Vector2::ZERO);
+        let err = py.eval("v / 0", None, Some(locals)).unwrap_err();
+        assert!(err.is_instance::<exceptions::ValueError>(py));
+    }
 
-        if let Ok(v) = other.extract::<Vector2>() {
-            let eq = v.x == self.x && v.y == self.y;
-            return PyBool::new(py, eq ^ cmp).into();
-        }
+    #[test]
+    fn imul_by_huge_scalar_raises_and_leaves_vector_unchanged() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let locals = locals(py, Vector2 { x: 0.0, y: 1.0 }, Vector2::ZERO);
+        let err = py.run("v *= 1e308 * 1e308", None, Some(locals)).unwrap_err();
+        assert!(err.is_instance::<exceptions::ValueError>(py));
 
-        match other.extract::<Vec<f64>>() {
-            Ok(vals) => {
-                let eq = vals.len() == 2
-                         && vals[0] == self.x
-                         && vals[1] == self.y;
-                PyBool::new(py, eq ^ cmp).into()
-            },
-            Err(_) => {
-                py.NotImplemented()
-            }
-        }
+        let v: Vector2 = locals.get_item("v").unwrap().extract().unwrap();
+        assert_eq!((v.x, v.y), (0.0, 1.0));
     }
-}
 
+    #[test]
+    fn iadd_and_isub_are_unguarded_for_huge_but_finite_operands() {
+        // Unlike the scalar multiply/divide operators, __iadd__/__isub__
+        // only ever combine two already-finite Vector2 instances, so the
+        // request's NaN/inf guard (which is specifically for scalar ops)
+        // does not apply to them; this documents that `+=`/`-=` of
+        // huge-but-finite vectors can still overflow to a non-finite result.
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let locals = locals(py, Vector2 { x: f64::MAX, y: 0.0 }, Vector2 { x: f64::MAX, y: 0.0 });
+        py.run("v += w", None, Some(locals)).unwrap();
+
+        let v: Vector2 = locals.get_item("v").unwrap().extract().unwrap();
+        assert!(v.x.is_infinite());
 
-#[pyproto]
-impl PyNumberProtocol for Vector2 {
-    fn __add__(lhs: PyRef<'p, Vector2>, rhs: PyRef<'p, Vector2>) -> Vector2 {
-        Vector2 {
-            x: lhs.x + rhs.x,
-            y: lhs.y + rhs.y,
-        }
+        let sub_locals = self::locals(py, Vector2 { x: -f64::MAX, y: 0.0 }, Vector2 { x: f64::MAX, y: 0.0 });
+        py.run("v -= w", None, Some(sub_locals)).unwrap();
+
+        let v: Vector2 = sub_locals.get_item("v").unwrap().extract().unwrap();
+        assert!(v.x.is_infinite());
     }
 
-   fn __mul__(lhs: PyRef<'p, Vector2>, rhs: f64) -> Vector2 {
-        Vector2 {
-            x: lhs.x * rhs,
-            y: lhs.y * rhs,
-        }
+    #[test]
+    fn cross_matches_2d_determinant() {
+        let x = Vector2 { x: 1.0, y: 0.0 };
+        let y = Vector2 { x: 0.0, y: 1.0 };
+        assert_eq!(x.cross(&y), 1.0);
+        assert_eq!(y.cross(&x), -1.0);
     }
-}
 
+    #[test]
+    fn rotate_quarter_turn_maps_x_to_y() {
+        let rotated = Vector2 { x: 1.0, y: 0.0 }.rotate(std::f64::consts::FRAC_PI_2);
+        assert!((rotated.x - 0.0).abs() < 1e-10);
+        assert!((rotated.y - 1.0).abs() < 1e-10);
+    }
 
-#[pyproto]
-impl PySequenceProtocol for Vector2 {
-    fn __len__(&self) -> usize {
-        2
+    #[test]
+    fn reflect_about_normal_flips_component_along_it() {
+        let v = Vector2 { x: 1.0, y: -1.0 };
+        let reflected = v.reflect(&Vector2 { x: 0.0, y: 1.0 });
+        assert_eq!((reflected.x, reflected.y), (1.0, 1.0));
     }
-}
 
+    #[test]
+    fn project_onto_axis_keeps_only_that_component() {
+        let v = Vector2 { x: 3.0, y: 4.0 };
+        let proj = v.project(&Vector2 { x: 1.0, y: 0.0 }).unwrap();
+        assert_eq!((proj.x, proj.y), (3.0, 0.0));
+    }
 
-#[pyclass]
-struct VecIter {
-    v: Vector2,
-    pos: usize,
-}
+    #[test]
+    fn project_onto_zero_vector_raises() {
+        let v = Vector2 { x: 3.0, y: 4.0 };
+        assert!(v.project(&Vector2 { x: 0.0, y: 0.0 }).is_err());
+    }
 
+    #[test]
+    fn angle_between_is_signed() {
+        let x = Vector2 { x: 1.0, y: 0.0 };
+        let y = Vector2 { x: 0.0, y: 1.0 };
+        assert!((x.angle_between(&y) - std::f64::consts::FRAC_PI_2).abs() < 1e-10);
+        assert!((y.angle_between(&x) + std::f64::consts::FRAC_PI_2).abs() < 1e-10);
+    }
 
-#[pyproto]
-impl PyIterProtocol for VecIter {
-    fn __iter__(slf: PyRef<Self>) -> Py<VecIter> {
-        slf.into()
+    #[test]
+    fn getitem_supports_positive_and_negative_indices() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let locals = locals(py, Vector2 { x: 1.0, y: 2.0 }, Vector2::ZERO);
+        assert_eq!(py.eval("v[0]", None, Some(locals)).unwrap().extract::<f64>().unwrap(), 1.0);
+        assert_eq!(py.eval("v[1]", None, Some(locals)).unwrap().extract::<f64>().unwrap(), 2.0);
+        assert_eq!(py.eval("v[-1]", None, Some(locals)).unwrap().extract::<f64>().unwrap(), 2.0);
+        assert_eq!(py.eval("v[-2]", None, Some(locals)).unwrap().extract::<f64>().unwrap(), 1.0);
     }
-    fn __next__(mut slf: PyRefMut<Self>) -> Option<f64> {
-        let res = match slf.pos {
-            0 => Some(slf.v.x),
-            1 => Some(slf.v.y),
-            _ => None,
-        };
-        slf.pos += 1;
-        res
+
+    #[test]
+    fn getitem_out_of_range_raises_index_error() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let locals = locals(py, Vector2 { x: 1.0, y: 2.0 }, Vector2::ZERO);
+        let err = py.eval("v[2]", None, Some(locals)).unwrap_err();
+        assert!(err.is_instance::<exceptions::IndexError>(py));
     }
-}
 
+    #[test]
+    fn getitem_slice_returns_list_of_components() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let locals = locals(py, Vector2 { x: 1.0, y: 2.0 }, Vector2::ZERO);
+        let reversed: Vec<f64> = py.eval("v[::-1]", None, Some(locals)).unwrap().extract().unwrap();
+        assert_eq!(reversed, vec![2.0, 1.0]);
 
-#[pyproto]
-impl PyIterProtocol for Vector2 {
-    fn __iter__(slf: PyRef<Self>) -> VecIter {
-        VecIter {
-            v: slf.clone(),
-            pos: 0
-        }
+        let all: Vec<f64> = py.eval("v[:]", None, Some(locals)).unwrap().extract().unwrap();
+        assert_eq!(all, vec![1.0, 2.0]);
     }
-}
 
+    #[test]
+    fn lerp_at_half_returns_midpoint() {
+        let a = Vector2 { x: 0.0, y: 0.0 };
+        let b = Vector2 { x: 2.0, y: 4.0 };
+        let mid = a.lerp(&b, 0.5);
+        assert_eq!((mid.x, mid.y), (1.0, 2.0));
+    }
 
+    #[test]
+    fn lerp_at_zero_and_one_returns_endpoints() {
+        let a = Vector2 { x: 0.0, y: 0.0 };
+        let b = Vector2 { x: 2.0, y: 4.0 };
+        assert_eq!((a.lerp(&b, 0.0).x, a.lerp(&b, 0.0).y), (0.0, 0.0));
+        assert_eq!((a.lerp(&b, 1.0).x, a.lerp(&b, 1.0).y), (2.0, 4.0));
+    }
 
-#[pymodule]
-fn wvec(_py: Python, m: &PyModule) -> PyResult<()> {
-    m.add_class::<Vector2>()?;
+    #[test]
+    fn distance_and_distance_squared_match_pythagorean_triple() {
+        let a = Vector2 { x: 0.0, y: 0.0 };
+        let b = Vector2 { x: 3.0, y: 4.0 };
+        assert_eq!(a.distance_squared(&b), 25.0);
+        assert_eq!(a.distance(&b), 5.0);
+    }
 
-    Ok(())
+    #[test]
+    fn clamp_length_scales_down_when_over_max() {
+        let v = Vector2 { x: 3.0, y: 4.0 };
+        let clamped = v.clamp_length(2.0);
+        assert!((clamped.length() - 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn clamp_length_is_noop_when_already_under_max() {
+        let v = Vector2 { x: 3.0, y: 4.0 };
+        let clamped = v.clamp_length(10.0);
+        assert_eq!((clamped.x, clamped.y), (3.0, 4.0));
+    }
+
+    #[test]
+    fn constants_have_expected_components() {
+        assert_eq!((Vector2::ZERO.x, Vector2::ZERO.y), (0.0, 0.0));
+        assert_eq!((Vector2::ONE.x, Vector2::ONE.y), (1.0, 1.0));
+        assert_eq!((Vector2::X.x, Vector2::X.y), (1.0, 0.0));
+        assert_eq!((Vector2::Y.x, Vector2::Y.y), (0.0, 1.0));
+    }
+
+    #[test]
+    fn min_and_max_are_componentwise() {
+        let a = Vector2 { x: 1.0, y: 4.0 };
+        let b = Vector2 { x: 3.0, y: 2.0 };
+        let min = a.min(&b);
+        let max = a.max(&b);
+        assert_eq!((min.x, min.y), (1.0, 2.0));
+        assert_eq!((max.x, max.y), (3.0, 4.0));
+    }
+
+    #[test]
+    fn abs_negates_negative_components_only() {
+        let v = Vector2 { x: -1.0, y: 2.0 }.abs();
+        assert_eq!((v.x, v.y), (1.0, 2.0));
+    }
+
+    #[test]
+    fn clamp_bounds_each_component_into_the_box() {
+        let v = Vector2 { x: -1.0, y: 5.0 };
+        let lo = Vector2 { x: 0.0, y: 0.0 };
+        let hi = Vector2 { x: 2.0, y: 2.0 };
+        let clamped = v.clamp(&lo, &hi);
+        assert_eq!((clamped.x, clamped.y), (0.0, 2.0));
+    }
+
+    #[test]
+    fn hash_is_consistent_with_eq_for_signed_zero() {
+        // 0.0 == -0.0 under __richcmp__, so __hash__ must agree or the
+        // hash/eq contract breaks (e.g. set/dict lookups silently fail).
+        let positive = Vector2 { x: 0.0, y: 0.0 };
+        let negative = Vector2 { x: -0.0, y: 0.0 };
+        assert!(positive.x == negative.x && positive.y == negative.y);
+        assert_eq!(positive.__hash__(), negative.__hash__());
+    }
+
+    #[test]
+    fn negated_zero_vector_is_found_in_a_set_of_zero() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let locals = locals(py, Vector2::ZERO, Vector2::ZERO);
+        let found: bool = py.eval("-v in {v}", None, Some(locals)).unwrap().extract().unwrap();
+        assert!(found);
+    }
+
+    #[test]
+    fn reduce_reconstructs_an_equal_vector() {
+        // __reduce__ is what pickle calls under the hood; exercise it the
+        // same way pickle does (type(*args)) without requiring the `wvec`
+        // module to be registered in sys.modules, as a real pickle.dumps
+        // round-trip would.
+        let v = Vector2 { x: 1.0, y: 2.0 };
+        let (ctor, args) = v.__reduce__().unwrap();
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let restored: Vector2 = ctor.as_ref(py).call1(args.extract::<&PyTuple>(py).unwrap())
+            .unwrap().extract().unwrap();
+        assert_eq!((restored.x, restored.y), (1.0, 2.0));
+    }
+
+    #[test]
+    fn copy_and_deepcopy_produce_an_equal_independent_vector() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let locals = locals(py, Vector2 { x: 1.0, y: 2.0 }, Vector2::ZERO);
+        let equal: bool = py.eval(
+            "__import__('copy').copy(v) == v and __import__('copy').deepcopy(v) == v \
+             and __import__('copy').copy(v) is not v and __import__('copy').deepcopy(v) is not v",
+            None,
+            Some(locals),
+        ).unwrap().extract().unwrap();
+        assert!(equal);
+    }
+
+    #[test]
+    fn vector3_cross_follows_right_hand_rule() {
+        let x = Vector3 { x: 1.0, y: 0.0, z: 0.0 };
+        let y = Vector3 { x: 0.0, y: 1.0, z: 0.0 };
+        let cross = x.cross(&y);
+        assert_eq!((cross.x, cross.y, cross.z), (0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn vector3_angle_between_is_unsigned() {
+        let x = Vector3 { x: 1.0, y: 0.0, z: 0.0 };
+        let y = Vector3 { x: 0.0, y: 1.0, z: 0.0 };
+        assert!((x.angle_between(&y) - std::f64::consts::FRAC_PI_2).abs() < 1e-10);
+        assert!((y.angle_between(&x) - std::f64::consts::FRAC_PI_2).abs() < 1e-10);
+    }
+
+    #[test]
+    fn vector3_equality_and_hash_share_the_macro_behaviour() {
+        let a = Vector3 { x: 1.0, y: 2.0, z: 3.0 };
+        let b = Vector3 { x: 1.0, y: 2.0, z: 3.0 };
+        assert!(a.x == b.x && a.y == b.y && a.z == b.z);
+        assert_eq!(a.__hash__(), b.__hash__());
+    }
+
+    #[test]
+    fn vector3_getitem_supports_indices_and_slicing() {
+        let gil = Python::acquire_gil();
+        let py = gil.python();
+        let v = PyCell::new(py, Vector3 { x: 1.0, y: 2.0, z: 3.0 }).unwrap();
+        let locals = [("v", v)].into_py_dict(py);
+        assert_eq!(py.eval("v[-1]", None, Some(locals)).unwrap().extract::<f64>().unwrap(), 3.0);
+        let all: Vec<f64> = py.eval("v[:]", None, Some(locals)).unwrap().extract().unwrap();
+        assert_eq!(all, vec![1.0, 2.0, 3.0]);
+    }
 }